@@ -3,79 +3,279 @@
 //! Invariants:
 //!
 //! - The first entry (index 0) will always be Vacant, and serves as the
-//! head of the free list. This allows us to use an Option<NonZeroU32>
+//! head of the free list. This allows us to use an Option<NonZeroU64>
 //! to save space
 //!
-//! - `Index` is also a NonZeroU32, where the highest 8 bits are used to
-//! store the generation of the entry, and the low 24 bits are represent
-//! the index of the `Entry` in the `Arena`. This puts a hard cap on the
-//! number of generations (255) and items (2^24 - 2) that can be stored
-//! in the `Arena`, but uses significantlly less space. This could be
-//! tuned to use 16 bits for both generation and index if necessary.
+//! - `Index` bit-packs a generation counter and a slot number into a
+//! single integer. The split between the two is controlled by the
+//! `Arena`'s `ArenaConfig` type parameter: `DefaultConfig` reproduces the
+//! original hard-coded layout (an 8-bit generation above a 24-bit slot),
+//! but a caller may plug in a different config to trade generation
+//! headroom for slot capacity, or to widen the backing integer for very
+//! large arenas.
+//!
+//! - Removing an item bumps the generation of its slot, so any `Index`
+//! obtained before the removal can never again resolve to the (possibly
+//! different) item that later occupies the same slot. A slot whose
+//! generation would wrap past the configured maximum is retired
+//! permanently rather than being returned to the free list.
 #![forbid(unsafe_code)]
 #![allow(dead_code)]
-use std::num::NonZeroU32;
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 const MIN_CAPACITY: u32 = 16;
 
-/// A generational arena allowing 255 generations, and 2^24 - 2 items
-pub struct Arena<T> {
+/// A primitive unsigned integer usable as the backing representation for
+/// a bit-packed `Index`, or for the generation counter exposed to callers
+pub trait PackedInt: Copy + Clone {
+    /// Total number of bits in the representation
+    const BITS: u32;
+
+    /// Truncate a `u64` down to this representation
+    fn from_u64(v: u64) -> Self;
+}
+
+macro_rules! impl_packed_int {
+    ($($t:ty),*) => {
+        $(
+            impl PackedInt for $t {
+                const BITS: u32 = <$t>::BITS;
+
+                fn from_u64(v: u64) -> Self {
+                    v as $t
+                }
+            }
+        )*
+    };
+}
+impl_packed_int!(u8, u16, u32, u64);
+
+/// Bit-layout configuration for a generational `Arena`'s `Index`.
+///
+/// `IndexRepr` names the unsigned integer the packed index is drawn
+/// from, bounding the total number of bits available to split between
+/// slot number and generation. `GenRepr` is the primitive used to expose
+/// the generation counter to callers via [`Index::gen`].
+pub trait ArenaConfig: Copy + Clone {
+    /// Backing integer bounding the total packed width
+    type IndexRepr: PackedInt;
+    /// Primitive used to expose the generation counter publicly
+    type GenRepr: PackedInt;
+
+    /// Number of low bits of `IndexRepr` given to the slot number; the
+    /// remaining high bits (up to `IndexRepr::BITS`) store the generation
+    const INDEX_BITS: u32;
+
+    /// Number of high bits given to the generation counter
+    fn gen_bits() -> u32 {
+        Self::IndexRepr::BITS - Self::INDEX_BITS
+    }
+
+    /// Mask selecting the slot bits of the packed representation
+    fn index_mask() -> u64 {
+        ((1u128 << Self::INDEX_BITS) - 1) as u64
+    }
+
+    /// Mask selecting the generation bits of the packed representation
+    fn gen_mask() -> u64 {
+        (((1u128 << Self::IndexRepr::BITS) - 1) as u64) & !Self::index_mask()
+    }
+
+    /// Maximum generation value representable in `gen_bits()` bits
+    fn max_gen() -> u64 {
+        Self::gen_mask() >> Self::INDEX_BITS
+    }
+}
+
+/// Reproduces the arena's original layout: an 8-bit generation packed
+/// above a 24-bit slot index in a 32-bit integer. Caps the arena at 255
+/// generations and 2^24 - 1 items. This is the default `ArenaConfig`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DefaultConfig;
+impl ArenaConfig for DefaultConfig {
+    type IndexRepr = u32;
+    type GenRepr = u8;
+    const INDEX_BITS: u32 = 24;
+}
+
+/// A 16-bit generation packed above a 16-bit slot index in a 32-bit
+/// integer: far fewer items (65,535) than `DefaultConfig`, but many more
+/// generations (65,535) before a slot is retired.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WideGenConfig;
+impl ArenaConfig for WideGenConfig {
+    type IndexRepr = u32;
+    type GenRepr = u16;
+    const INDEX_BITS: u32 = 16;
+}
+
+/// A 64-bit backing integer with a 32-bit generation packed above a
+/// 32-bit slot index, for arenas holding billions of items.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LargeConfig;
+impl ArenaConfig for LargeConfig {
+    type IndexRepr = u64;
+    type GenRepr = u32;
+    const INDEX_BITS: u32 = 32;
+}
+
+/// A generational arena, bit-packing a generation counter and slot number
+/// into each `Index` according to `C`. Defaults to `DefaultConfig` (255
+/// generations, 2^24 - 1 items), matching the arena's original layout.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Arena<T, C: ArenaConfig = DefaultConfig> {
     data: Vec<Entry<T>>,
     len: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _config: PhantomData<C>,
 }
 
 /// Entry in an Arena<T>
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 enum Entry<T> {
-    /// Vacant entry contains a pointer to the next free vacant entry
-    Vacant(Option<NonZeroU32>),
+    /// Vacant entry contains the slot's current generation and a pointer
+    /// to the next free vacant entry, so the generation survives while
+    /// the slot is unoccupied
+    Vacant(u64, Option<NonZeroU64>),
     /// Occupied entry contains a generation count and a value
-    Occupied(u8, T),
+    Occupied(u64, T),
 }
 
-/// `Index` into an `Arena`, with bitpacked generation and index values
-#[derive(Copy, Clone)]
-pub struct Index(NonZeroU32);
-impl Index {
-    fn gen(&self) -> u8 {
-        let mask = 0xFF00_0000;
-        ((self.0.get() & mask) >> 24) as u8
+/// `Index` into an `Arena<T, C>`, with bitpacked generation and index
+/// values, laid out according to `C`
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Index<C: ArenaConfig = DefaultConfig> {
+    bits: NonZeroU64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _config: PhantomData<C>,
+}
+
+// Implemented by hand rather than derived, so that `Index<C>` remains
+// `Copy`/`Eq`/etc. regardless of whether the zero-sized config marker
+// `C` itself implements them.
+impl<C: ArenaConfig> Copy for Index<C> {}
+impl<C: ArenaConfig> Clone for Index<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<C: ArenaConfig> PartialEq for Index<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+impl<C: ArenaConfig> Eq for Index<C> {}
+impl<C: ArenaConfig> std::hash::Hash for Index<C> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.bits.hash(state)
+    }
+}
+impl<C: ArenaConfig> std::fmt::Debug for Index<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("Index").field(&self.bits).finish()
+    }
+}
+
+impl<C: ArenaConfig> Index<C> {
+    fn gen_raw(&self) -> u64 {
+        (self.bits.get() & C::gen_mask()) >> C::INDEX_BITS
+    }
+
+    /// The generation counter packed into this `Index`
+    pub fn gen(&self) -> C::GenRepr {
+        C::GenRepr::from_u64(self.gen_raw())
     }
 
-    fn pair(self) -> (u8, u32) {
-        let mask = 0xFF00_0000;
-        let gen = ((self.0.get() & mask) >> 24) as u8;
-        let idx = self.0.get() & !mask;
+    fn pair(self) -> (u64, u64) {
+        let gen = self.gen_raw();
+        let idx = self.bits.get() & C::index_mask();
         (gen, idx)
     }
 
-    fn new(gen: u8, index: NonZeroU32) -> Index {
-        Index(NonZeroU32::new(index.get() | ((gen as u32) << 24)).unwrap())
+    fn new(gen: u64, index: NonZeroU64) -> Index<C> {
+        let packed = (index.get() & C::index_mask()) | ((gen << C::INDEX_BITS) & C::gen_mask());
+        Index {
+            bits: NonZeroU64::new(packed).unwrap(),
+            _config: PhantomData,
+        }
+    }
+
+    /// Convert this `Index` into an opaque `u64` bit pattern, suitable for
+    /// handing to C code, storing in external maps, or serializing as a
+    /// plain integer.
+    pub fn to_bits(self) -> u64 {
+        self.bits.get()
+    }
+
+    /// Reconstruct an `Index` from a `u64` previously produced by
+    /// [`Index::to_bits`] for the same config `C`.
+    ///
+    /// Returns `None` if `bits` does not describe a valid index: if any
+    /// bit outside `C`'s configured width is set, or if the packed slot
+    /// is zero, which is reserved for the free list head and can never
+    /// be a live `Index`.
+    pub fn from_bits(bits: u64) -> Option<Index<C>> {
+        let slot = bits & C::index_mask();
+        if slot == 0 {
+            return None;
+        }
+        let total_mask = C::index_mask() | C::gen_mask();
+        if bits & !total_mask != 0 {
+            return None;
+        }
+        Some(Index {
+            bits: NonZeroU64::new(bits).unwrap(),
+            _config: PhantomData,
+        })
     }
 }
 
-impl<T> Arena<T> {
-    fn with_capacity(n: u32) -> Arena<T> {
-        assert!(n & 0xFF00_0000 == 0);
+impl<T, C: ArenaConfig> Arena<T, C> {
+    pub fn with_capacity(n: u32) -> Arena<T, C> {
+        assert!(
+            (n as u64) <= C::index_mask(),
+            "requested capacity exceeds the configured index width"
+        );
         let mut arena = Arena {
-            data: vec![Entry::Vacant(None)],
+            data: vec![Entry::Vacant(0, None)],
             len: 0,
+            _config: PhantomData,
         };
         arena.reserve(n);
         arena
     }
 
-    fn next_free(&self) -> Option<NonZeroU32> {
+    /// The number of occupied entries in the `Arena`
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns `true` if the `Arena` contains no occupied entries
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if `index` refers to a currently-occupied entry
+    pub fn contains(&self, index: Index<C>) -> bool {
+        self.get(index).is_some()
+    }
+
+    fn next_free(&self) -> Option<NonZeroU64> {
         match self.data.get(0) {
-            Some(Entry::Vacant(ref next)) => *next,
+            Some(Entry::Vacant(_, next)) => *next,
             _ => None,
         }
     }
 
-    fn set_free(&mut self, index: NonZeroU32) {
-        self.data[0] = Entry::Vacant(Some(index))
+    fn set_free(&mut self, index: NonZeroU64) {
+        self.data[0] = Entry::Vacant(0, Some(index))
     }
 
-    fn get(&self, index: Index) -> Option<&T> {
+    pub fn get(&self, index: Index<C>) -> Option<&T> {
         let (gen, idx) = index.pair();
         match self.data.get(idx as usize) {
             Some(Entry::Occupied(g, val)) if *g == gen => Some(val),
@@ -83,42 +283,567 @@ impl<T> Arena<T> {
         }
     }
 
-    fn try_insert(&mut self, item: T) -> Option<Index> {
+    pub fn get_mut(&mut self, index: Index<C>) -> Option<&mut T> {
+        let (gen, idx) = index.pair();
+        match self.data.get_mut(idx as usize) {
+            Some(Entry::Occupied(g, val)) if *g == gen => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn try_insert(&mut self, item: T) -> Option<Index<C>> {
         let idx = self.next_free()?;
         let free = idx.get() as usize;
         match self.data[free] {
             Entry::Occupied(_, _) => panic!("Corrupted free list"),
-            Entry::Vacant(next) => {
-                self.data[0] = Entry::Vacant(next);
-                self.data[free] = Entry::Occupied(0, item);
-                Some(Index(idx))
+            Entry::Vacant(gen, next) => {
+                self.data[0] = Entry::Vacant(0, next);
+                self.data[free] = Entry::Occupied(gen, item);
+                self.len += 1;
+                Some(Index::new(gen, idx))
+            }
+        }
+    }
+
+    /// Remove the item at `index`, returning `Some(item)` if `index`'s
+    /// generation matches the slot's current generation, or `None` if the
+    /// slot is vacant or `index` is stale.
+    ///
+    /// Bumps the slot's generation so any other outstanding `Index` into
+    /// this slot is invalidated. If the generation would wrap past `C`'s
+    /// configured maximum, the slot is retired permanently instead of
+    /// being returned to the free list.
+    pub fn remove(&mut self, index: Index<C>) -> Option<T> {
+        let (gen, idx) = index.pair();
+        let slot = idx as usize;
+        match self.data.get(slot) {
+            Some(Entry::Occupied(g, _)) if *g == gen => {}
+            _ => return None,
+        }
+
+        let item = match std::mem::replace(&mut self.data[slot], Entry::Vacant(gen, None)) {
+            Entry::Occupied(_, item) => item,
+            Entry::Vacant(..) => unreachable!("checked above"),
+        };
+
+        if gen == C::max_gen() {
+            // The generation counter is exhausted: retire the slot rather
+            // than wrapping, so a recycled slot can never collide with an
+            // outstanding stale `Index`.
+            self.data[slot] = Entry::Vacant(gen, None);
+        } else {
+            let next_gen = gen + 1;
+            let head = self.next_free();
+            self.data[slot] = Entry::Vacant(next_gen, head);
+            self.set_free(NonZeroU64::new(slot as u64).unwrap());
+        }
+
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// Retain only the occupied entries for which `f` returns `true`,
+    /// removing the rest (and bumping their generation) in place
+    pub fn retain<F: FnMut(Index<C>, &mut T) -> bool>(&mut self, mut f: F) {
+        let indices: Vec<Index<C>> = self.iter().map(|(index, _)| index).collect();
+        for index in indices {
+            let keep = f(index, self.get_mut(index).unwrap());
+            if !keep {
+                self.remove(index);
             }
         }
     }
 
+    /// Remove every occupied entry, rebuilding a single contiguous free
+    /// list over the existing capacity without deallocating.
+    ///
+    /// Bumps the generation of every discarded occupied slot, so a stale
+    /// `Index` held from before the clear can never alias whatever gets
+    /// inserted into the same slot afterward. Every vacant slot left at
+    /// `C`'s maximum generation is then retired, including one that only
+    /// just reached it and that `remove` would still have reused once.
+    pub fn clear(&mut self) {
+        for slot in 1..self.data.len() {
+            if let Entry::Occupied(gen, _) = self.data[slot] {
+                let next_gen = if gen == C::max_gen() { gen } else { gen + 1 };
+                self.data[slot] = Entry::Vacant(next_gen, None);
+            }
+        }
+        self.relink_free_list();
+        self.len = 0;
+    }
+
+    /// Return disjoint mutable references to the items at `a` and `b`.
+    ///
+    /// Returns `None` if `a == b`, or if either index is vacant or stale,
+    /// by splitting the backing storage rather than requiring `unsafe` or
+    /// a clone.
+    pub fn get2_mut(&mut self, a: Index<C>, b: Index<C>) -> Option<(&mut T, &mut T)> {
+        if a == b {
+            return None;
+        }
+
+        let (gen_a, ia) = a.pair();
+        let (gen_b, ib) = b.pair();
+        let (ia, ib) = (ia as usize, ib as usize);
+        if ia == ib {
+            return None;
+        }
+
+        let (lower, higher, lower_gen, higher_gen) = if ia < ib {
+            (ia, ib, gen_a, gen_b)
+        } else {
+            (ib, ia, gen_b, gen_a)
+        };
+        let (left, right) = self.data.split_at_mut(higher);
+
+        let lower_val = match &mut left[lower] {
+            Entry::Occupied(g, t) if *g == lower_gen => t,
+            _ => return None,
+        };
+        let higher_val = match &mut right[0] {
+            Entry::Occupied(g, t) if *g == higher_gen => t,
+            _ => return None,
+        };
+
+        if ia < ib {
+            Some((lower_val, higher_val))
+        } else {
+            Some((higher_val, lower_val))
+        }
+    }
+
     fn reserve(&mut self, n: u32) {
-        let start = self.data.len() as u32;
-        let end = start + n;
+        let start = self.data.len() as u64;
+        let end = start + n as u64;
         let free = self.next_free();
 
         self.data.reserve(n as usize);
         self.data.extend((start..end).map(|idx| {
             if idx == end - 1 {
-                Entry::Vacant(free)
+                Entry::Vacant(0, free)
             } else {
-                Entry::Vacant(Some(NonZeroU32::new(idx + 1).unwrap()))
+                Entry::Vacant(0, Some(NonZeroU64::new(idx + 1).unwrap()))
             }
         }));
-        self.set_free(NonZeroU32::new(start).unwrap());
+        self.set_free(NonZeroU64::new(start).unwrap());
+    }
+
+    /// Grow the backing storage to its full reserved capacity (appending
+    /// fresh generation-0 slots), then relink every vacant slot whose
+    /// generation is below `C`'s maximum into a single free list,
+    /// preserving each slot's current generation rather than resetting it.
+    /// Vacant slots at the maximum generation are left out, retiring them.
+    ///
+    /// Used by `clear` and `drain`, which first turn every occupied slot
+    /// into a vacant one with a bumped generation, then call this to
+    /// rebuild the free list around the result.
+    fn relink_free_list(&mut self) {
+        let target = self.data.capacity() as u32 - 1;
+        let additional = target.saturating_sub(self.data.len() as u32 - 1);
+        self.data.reserve(additional as usize);
+        self.data
+            .extend((0..additional).map(|_| Entry::Vacant(0, None)));
+
+        let mut next = None;
+        for slot in (1..self.data.len()).rev() {
+            if let Entry::Vacant(gen, _) = self.data[slot] {
+                if gen == C::max_gen() {
+                    continue;
+                }
+                self.data[slot] = Entry::Vacant(gen, next);
+                next = NonZeroU64::new(slot as u64);
+            }
+        }
+        self.data[0] = Entry::Vacant(0, next);
+    }
+
+    /// Iterate over every occupied entry, yielding its `Index` alongside
+    /// a shared reference to the item
+    pub fn iter(&self) -> impl Iterator<Item = (Index<C>, &T)> {
+        self.data.iter().enumerate().filter_map(|(i, e)| match e {
+            Entry::Occupied(gen, t) => {
+                Some((Index::new(*gen, NonZeroU64::new(i as u64).unwrap()), t))
+            }
+            _ => None,
+        })
+    }
+
+    /// Iterate over every occupied entry, yielding its `Index` alongside
+    /// a mutable reference to the item
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index<C>, &mut T)> {
+        self.data
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, e)| match e {
+                Entry::Occupied(gen, t) => {
+                    Some((Index::new(*gen, NonZeroU64::new(i as u64).unwrap()), t))
+                }
+                _ => None,
+            })
+    }
+
+    /// Remove every occupied entry from the `Arena`, returning an iterator
+    /// over the removed `(Index, T)` pairs.
+    ///
+    /// After draining, the `Arena` is empty but retains its capacity, with
+    /// a freshly rebuilt free list, so it can be reused immediately. Every
+    /// drained slot's generation is bumped rather than reset, so a stale
+    /// `Index` for an item that was just drained can never alias whatever
+    /// is inserted next into the same slot. As with `clear`, every vacant
+    /// slot left at `C`'s maximum generation is retired.
+    pub fn drain(&mut self) -> impl Iterator<Item = (Index<C>, T)> {
+        let mut drained = Vec::with_capacity(self.len as usize);
+        for slot in 1..self.data.len() {
+            if let Entry::Occupied(gen, _) = self.data[slot] {
+                let item = match std::mem::replace(&mut self.data[slot], Entry::Vacant(gen, None))
+                {
+                    Entry::Occupied(_, item) => item,
+                    Entry::Vacant(..) => unreachable!("checked above"),
+                };
+                drained.push((Index::new(gen, NonZeroU64::new(slot as u64).unwrap()), item));
+
+                let next_gen = if gen == C::max_gen() { gen } else { gen + 1 };
+                self.data[slot] = Entry::Vacant(next_gen, None);
+            }
+        }
+        self.relink_free_list();
+        self.len = 0;
+        drained.into_iter()
+    }
+}
+
+/// Owning iterator over an `Arena`'s occupied entries, yielding `(Index, T)`
+pub struct IntoIter<T, C: ArenaConfig = DefaultConfig> {
+    inner: std::iter::Enumerate<std::vec::IntoIter<Entry<T>>>,
+    _config: PhantomData<C>,
+}
+
+impl<T, C: ArenaConfig> Iterator for IntoIter<T, C> {
+    type Item = (Index<C>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, e) in &mut self.inner {
+            if let Entry::Occupied(gen, t) = e {
+                return Some((Index::new(gen, NonZeroU64::new(i as u64).unwrap()), t));
+            }
+        }
+        None
+    }
+}
+
+impl<T, C: ArenaConfig> IntoIterator for Arena<T, C> {
+    type Item = (Index<C>, T);
+    type IntoIter = IntoIter<T, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.data.into_iter().enumerate(),
+            _config: PhantomData,
+        }
+    }
+}
+
+/// Walk the free list rooted at `data[0]`, verifying that every vacant
+/// `next` pointer leads to another vacant, in-bounds slot with no cycles.
+///
+/// Used to reject a malformed serialized `Arena` instead of silently
+/// deserializing into a corrupted one.
+#[cfg(feature = "serde")]
+fn validate_free_list<T>(data: &[Entry<T>]) -> Result<(), String> {
+    let mut cursor = match data.first() {
+        Some(Entry::Vacant(_, next)) => *next,
+        Some(Entry::Occupied(..)) => return Err("index 0 must be vacant".to_string()),
+        None => return Err("arena data must not be empty".to_string()),
+    };
+
+    let mut seen = vec![false; data.len()];
+    seen[0] = true;
+    while let Some(index) = cursor {
+        let i = index.get() as usize;
+        if i >= data.len() {
+            return Err(format!("free list entry {} is out of bounds", i));
+        }
+        if seen[i] {
+            return Err(format!("cycle detected in free list at slot {}", i));
+        }
+        seen[i] = true;
+        cursor = match &data[i] {
+            Entry::Vacant(_, next) => *next,
+            Entry::Occupied(..) => return Err(format!("free list points at occupied slot {}", i)),
+        };
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>, C: ArenaConfig> Deserialize<'de> for Arena<T, C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw<T> {
+            data: Vec<Entry<T>>,
+            len: u32,
+        }
+
+        let raw = Raw::<T>::deserialize(deserializer)?;
+        validate_free_list(&raw.data).map_err(serde::de::Error::custom)?;
+
+        let occupied = raw
+            .data
+            .iter()
+            .filter(|e| matches!(e, Entry::Occupied(..)))
+            .count() as u32;
+        if occupied != raw.len {
+            return Err(serde::de::Error::custom(format!(
+                "stored len {} does not match {} occupied entries",
+                raw.len, occupied
+            )));
+        }
+
+        Ok(Arena {
+            data: raw.data,
+            len: raw.len,
+            _config: PhantomData,
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+
     #[test]
     fn new() {
         let a = Arena::<u32>::with_capacity(256);
         assert_eq!(a.next_free().map(|n| n.get()), Some(1))
     }
+
+    #[test]
+    fn remove_invalidates_stale_index() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let idx = arena.try_insert(10).unwrap();
+        assert_eq!(arena.remove(idx), Some(10));
+        assert_eq!(arena.get(idx), None);
+        assert_eq!(arena.len(), 0);
+
+        let reused = arena.try_insert(20).unwrap();
+        assert_eq!(arena.get(idx), None);
+        assert_eq!(arena.get(reused), Some(&20));
+    }
+
+    #[test]
+    fn generation_retires_slot_at_max() {
+        let mut arena = Arena::<u32>::with_capacity(16);
+        let mut idx = arena.try_insert(0u32).unwrap();
+        for _ in 0..255 {
+            arena.remove(idx).unwrap();
+            idx = arena.try_insert(0u32).unwrap();
+        }
+        assert_eq!(idx.gen(), 255);
+
+        // The slot's generation has reached the maximum; removing it one
+        // more time must retire the slot rather than reuse it.
+        let (_, slot) = idx.pair();
+        arena.remove(idx).unwrap();
+        let reused = arena.try_insert(1u32).unwrap();
+        assert_ne!(reused.pair().1, slot);
+    }
+
+    #[test]
+    fn bits_round_trip() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let idx = arena.try_insert(42).unwrap();
+        assert_eq!(Index::from_bits(idx.to_bits()), Some(idx));
+
+        // Upper bits set, and the reserved slot-0 free-list head, are
+        // both invalid encodings.
+        assert_eq!(Index::<DefaultConfig>::from_bits((1u64 << 32) | 1), None);
+        assert_eq!(Index::<DefaultConfig>::from_bits(0), None);
+    }
+
+    #[test]
+    fn retain_bumps_generation_on_removed() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let indices: Vec<Index> = (0..5).map(|i| arena.try_insert(i).unwrap()).collect();
+        arena.retain(|_, v| *v % 2 == 0);
+        assert_eq!(arena.len(), 3);
+        for (i, index) in indices.iter().enumerate() {
+            let expected = i as i32;
+            assert_eq!(arena.get(*index), if i % 2 == 0 { Some(&expected) } else { None });
+        }
+
+        // The two freed slots are reused next, at a bumped generation, and
+        // the stale indices into them still resolve to nothing.
+        let r1 = arena.try_insert(100).unwrap();
+        let r2 = arena.try_insert(101).unwrap();
+        assert_eq!((r1.gen(), r2.gen()), (1, 1));
+        assert_eq!(arena.get(indices[1]), None);
+        assert_eq!(arena.get(indices[3]), None);
+    }
+
+    #[test]
+    fn clear_keeps_capacity_and_resets_len() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        for i in 0..5 {
+            arena.try_insert(i).unwrap();
+        }
+        let cap = arena.data.capacity();
+        arena.clear();
+        assert_eq!(arena.data.capacity(), cap);
+        assert_eq!(arena.len(), 0);
+        assert_eq!(arena.iter().count(), 0);
+    }
+
+    #[test]
+    fn clear_invalidates_stale_index_on_reuse() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let stale = arena.try_insert(1).unwrap();
+        arena.clear();
+        let reused = arena.try_insert(2).unwrap();
+        assert_eq!(stale.pair().1, reused.pair().1);
+        assert_eq!(arena.get(stale), None);
+        assert_eq!(arena.get(reused), Some(&2));
+    }
+
+    #[test]
+    fn get2_mut_rejects_stale_and_equal() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        {
+            let (ra, rb) = arena.get2_mut(a, b).unwrap();
+            *ra += 10;
+            *rb += 20;
+        }
+        assert_eq!(arena.get(a), Some(&11));
+        assert_eq!(arena.get(b), Some(&22));
+        assert!(arena.get2_mut(a, a).is_none());
+
+        arena.remove(a).unwrap();
+        assert!(arena.get2_mut(a, b).is_none());
+    }
+
+    #[test]
+    fn wide_gen_config_allows_more_generations_fewer_slots() {
+        let mut arena = Arena::<u32, WideGenConfig>::with_capacity(16);
+        let idx = arena.try_insert(1).unwrap();
+        assert_eq!(WideGenConfig::max_gen(), u16::MAX as u64);
+        assert!(WideGenConfig::index_mask() < DefaultConfig::index_mask());
+        assert!(arena.get(idx).is_some());
+    }
+
+    #[test]
+    fn iter_yields_index_and_value() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let indices: Vec<Index> = (0..5).map(|i| arena.try_insert(i).unwrap()).collect();
+        let collected: Vec<(Index, i32)> = arena.iter().map(|(i, v)| (i, *v)).collect();
+        assert_eq!(collected.len(), 5);
+        for index in indices {
+            assert!(collected.iter().any(|(i, _)| *i == index));
+        }
+    }
+
+    #[test]
+    fn iter_mut_allows_updating_in_place() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        for i in 0..5 {
+            arena.try_insert(i).unwrap();
+        }
+        for (_, v) in arena.iter_mut() {
+            *v *= 10;
+        }
+        let mut values: Vec<i32> = arena.iter().map(|(_, v)| *v).collect();
+        values.sort();
+        assert_eq!(values, vec![0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn into_iter_consumes_arena() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        for i in 0..5 {
+            arena.try_insert(i).unwrap();
+        }
+        let mut values: Vec<i32> = arena.into_iter().map(|(_, v)| v).collect();
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn drain_empties_arena_and_is_reusable() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        for i in 0..5 {
+            arena.try_insert(i).unwrap();
+        }
+        let mut drained: Vec<i32> = arena.drain().map(|(_, v)| v).collect();
+        drained.sort();
+        assert_eq!(drained, vec![0, 1, 2, 3, 4]);
+        assert_eq!(arena.iter().count(), 0);
+
+        let reused = arena.try_insert(42).unwrap();
+        assert_eq!(arena.get(reused), Some(&42));
+    }
+
+    #[test]
+    fn drain_invalidates_stale_index_on_reuse() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let stale = arena.try_insert(1).unwrap();
+        let _: Vec<_> = arena.drain().collect();
+        let reused = arena.try_insert(2).unwrap();
+        assert_eq!(stale.pair().1, reused.pair().1);
+        assert_eq!(arena.get(stale), None);
+        assert_eq!(arena.get(reused), Some(&2));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_test {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Raw<T> {
+        data: Vec<Entry<T>>,
+        len: u32,
+    }
+
+    #[test]
+    fn round_trips_outstanding_indices() {
+        let mut arena = Arena::<i32>::with_capacity(16);
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        arena.remove(a).unwrap();
+        let c = arena.try_insert(3).unwrap();
+
+        let json = serde_json::to_string(&arena).unwrap();
+        let restored: Arena<i32> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.get(b), Some(&2));
+        assert_eq!(restored.get(c), Some(&3));
+        assert_eq!(restored.get(a), None);
+    }
+
+    #[test]
+    fn rejects_free_list_cycle() {
+        let corrupt = Raw {
+            data: vec![
+                Entry::<i32>::Vacant(0, NonZeroU64::new(1)),
+                Entry::<i32>::Vacant(0, NonZeroU64::new(1)),
+            ],
+            len: 0,
+        };
+        let json = serde_json::to_string(&corrupt).unwrap();
+        assert!(serde_json::from_str::<Arena<i32>>(&json).is_err());
+    }
+
+    #[test]
+    fn rejects_occupied_slot_zero() {
+        let corrupt = Raw {
+            data: vec![Entry::Occupied(0, 42)],
+            len: 1,
+        };
+        let json = serde_json::to_string(&corrupt).unwrap();
+        assert!(serde_json::from_str::<Arena<i32>>(&json).is_err());
+    }
 }
@@ -36,20 +36,26 @@
 
 use std::num::NonZeroU32;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Minimum capacity for an `Arena`
 pub const MIN_CAPACITY: u32 = 16;
 
 /// The `Arena`, an allocator
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Arena<T> {
     data: Vec<Entry<T>>,
 }
 
 /// An index into an `Arena`
-#[derive(PartialEq, PartialOrd, Debug, Copy, Clone)]
+#[derive(PartialEq, PartialOrd, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Index(NonZeroU32);
 
 /// Internal entry data structure
 #[derive(PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 enum Entry<T> {
     Vacant(Option<NonZeroU32>),
     Occupied(T),
@@ -225,14 +231,174 @@ impl<T> Arena<T> {
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.data.iter().filter_map(|e| match e {
-            Entry::Occupied(t) => Some(t),
+    /// Retain only the occupied entries for which `f` returns `true`,
+    /// removing the rest (and freeing their slots) in place
+    pub fn retain<F: FnMut(Index, &mut T) -> bool>(&mut self, mut f: F) {
+        let indices: Vec<Index> = self.iter().map(|(index, _)| index).collect();
+        for index in indices {
+            let keep = f(index, self.get_mut(index).unwrap());
+            if !keep {
+                self.remove(index);
+            }
+        }
+    }
+
+    /// Remove every occupied entry, rebuilding a single contiguous free
+    /// list over the existing capacity without deallocating
+    pub fn clear(&mut self) {
+        let cap = self.capacity();
+        self.data.clear();
+        self.data.push(Entry::Vacant(None));
+        self.reserve(cap.max(MIN_CAPACITY));
+    }
+
+    /// Return disjoint mutable references to the items at `a` and `b`.
+    ///
+    /// Returns `None` if `a == b`, or if either index is vacant, by
+    /// splitting the backing storage rather than requiring `unsafe` or a
+    /// clone.
+    pub fn get2_mut(&mut self, a: Index, b: Index) -> Option<(&mut T, &mut T)> {
+        if a == b {
+            return None;
+        }
+
+        let ia = a.0.get() as usize;
+        let ib = b.0.get() as usize;
+        let (lower, higher) = if ia < ib { (ia, ib) } else { (ib, ia) };
+        let (left, right) = self.data.split_at_mut(higher);
+
+        let lower_val = match &mut left[lower] {
+            Entry::Occupied(t) => t,
+            Entry::Vacant(_) => return None,
+        };
+        let higher_val = match &mut right[0] {
+            Entry::Occupied(t) => t,
+            Entry::Vacant(_) => return None,
+        };
+
+        if ia < ib {
+            Some((lower_val, higher_val))
+        } else {
+            Some((higher_val, lower_val))
+        }
+    }
+
+    /// Iterate over every occupied entry, yielding its `Index` alongside
+    /// a shared reference to the item
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
+        self.data.iter().enumerate().filter_map(|(i, e)| match e {
+            Entry::Occupied(t) => Some((Index(NonZeroU32::new(i as u32).unwrap()), t)),
+            _ => None,
+        })
+    }
+
+    /// Iterate over every occupied entry, yielding its `Index` alongside
+    /// a mutable reference to the item
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut T)> {
+        self.data
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, e)| match e {
+                Entry::Occupied(t) => Some((Index(NonZeroU32::new(i as u32).unwrap()), t)),
+                _ => None,
+            })
+    }
+
+    /// Remove every occupied entry from the `Arena`, returning an iterator
+    /// over the removed `(Index, T)` pairs.
+    ///
+    /// After draining, the `Arena` is empty but retains its capacity, with
+    /// a freshly rebuilt free list, so it can be reused immediately.
+    pub fn drain(&mut self) -> impl Iterator<Item = (Index, T)> {
+        let cap = self.capacity();
+        let old = std::mem::replace(&mut self.data, vec![Entry::Vacant(None)]);
+        self.reserve(cap.max(MIN_CAPACITY));
+
+        old.into_iter().enumerate().filter_map(|(i, e)| match e {
+            Entry::Occupied(t) => Some((Index(NonZeroU32::new(i as u32).unwrap()), t)),
             _ => None,
         })
     }
 }
 
+/// Owning iterator over an `Arena`'s occupied entries, yielding `(Index, T)`
+pub struct IntoIter<T> {
+    inner: std::iter::Enumerate<std::vec::IntoIter<Entry<T>>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (Index, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, e) in &mut self.inner {
+            if let Entry::Occupied(t) = e {
+                return Some((Index(NonZeroU32::new(i as u32).unwrap()), t));
+            }
+        }
+        None
+    }
+}
+
+impl<T> IntoIterator for Arena<T> {
+    type Item = (Index, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.data.into_iter().enumerate(),
+        }
+    }
+}
+
+/// Walk the free list rooted at `data[0]`, verifying that every vacant
+/// `next` pointer leads to another vacant, in-bounds slot with no cycles.
+///
+/// Used to reject a malformed serialized `Arena` instead of silently
+/// deserializing into a corrupted one.
+#[cfg(feature = "serde")]
+fn validate_free_list<T>(data: &[Entry<T>]) -> Result<(), String> {
+    let mut cursor = match data.first() {
+        Some(Entry::Vacant(next)) => *next,
+        Some(Entry::Occupied(_)) => return Err("index 0 must be vacant".to_string()),
+        None => return Err("arena data must not be empty".to_string()),
+    };
+
+    let mut seen = vec![false; data.len()];
+    seen[0] = true;
+    while let Some(index) = cursor {
+        let i = index.get() as usize;
+        if i >= data.len() {
+            return Err(format!("free list entry {} is out of bounds", i));
+        }
+        if seen[i] {
+            return Err(format!("cycle detected in free list at slot {}", i));
+        }
+        seen[i] = true;
+        cursor = match &data[i] {
+            Entry::Vacant(next) => *next,
+            Entry::Occupied(_) => return Err(format!("free list points at occupied slot {}", i)),
+        };
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Arena<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw<T> {
+            data: Vec<Entry<T>>,
+        }
+
+        let raw = Raw::<T>::deserialize(deserializer)?;
+        validate_free_list(&raw.data).map_err(serde::de::Error::custom)?;
+        Ok(Arena { data: raw.data })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -250,4 +416,137 @@ mod test {
         assert_eq!(arena.capacity(), MIN_CAPACITY);
     }
 
+    #[test]
+    fn retain_removes_non_matching() {
+        let mut arena = Arena::new();
+        let indices: Vec<Index> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.retain(|_, v| *v % 2 == 0);
+        for (i, index) in indices.iter().enumerate() {
+            assert_eq!(arena.get(*index), if i % 2 == 0 { Some(&i) } else { None });
+        }
+    }
+
+    #[test]
+    fn clear_keeps_capacity() {
+        let mut arena = Arena::new();
+        for i in 0..5 {
+            arena.insert(i);
+        }
+        let cap = arena.capacity();
+        arena.clear();
+        assert_eq!(arena.capacity(), cap);
+        assert_eq!(arena.iter().count(), 0);
+    }
+
+    #[test]
+    fn get2_mut_disjoint() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        {
+            let (ra, rb) = arena.get2_mut(a, b).unwrap();
+            *ra += 10;
+            *rb += 20;
+        }
+        assert_eq!(arena.get(a), Some(&11));
+        assert_eq!(arena.get(b), Some(&22));
+        assert!(arena.get2_mut(a, a).is_none());
+    }
+
+    #[test]
+    fn iter_yields_index_and_value() {
+        let mut arena = Arena::new();
+        let indices: Vec<Index> = (0..5).map(|i| arena.insert(i)).collect();
+        let collected: Vec<(Index, i32)> = arena.iter().map(|(i, v)| (i, *v)).collect();
+        assert_eq!(collected.len(), 5);
+        for index in indices {
+            assert!(collected.iter().any(|(i, _)| *i == index));
+        }
+    }
+
+    #[test]
+    fn iter_mut_allows_updating_in_place() {
+        let mut arena = Arena::new();
+        for i in 0..5 {
+            arena.insert(i);
+        }
+        for (_, v) in arena.iter_mut() {
+            *v *= 10;
+        }
+        let mut values: Vec<i32> = arena.iter().map(|(_, v)| *v).collect();
+        values.sort();
+        assert_eq!(values, vec![0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn into_iter_consumes_arena() {
+        let mut arena = Arena::new();
+        for i in 0..5 {
+            arena.insert(i);
+        }
+        let mut values: Vec<i32> = arena.into_iter().map(|(_, v)| v).collect();
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn drain_empties_arena_and_is_reusable() {
+        let mut arena = Arena::new();
+        for i in 0..5 {
+            arena.insert(i);
+        }
+        let mut drained: Vec<i32> = arena.drain().map(|(_, v)| v).collect();
+        drained.sort();
+        assert_eq!(drained, vec![0, 1, 2, 3, 4]);
+        assert_eq!(arena.iter().count(), 0);
+
+        let reused = arena.insert(42);
+        assert_eq!(arena.get(reused), Some(&42));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_test {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Raw<T> {
+        data: Vec<Entry<T>>,
+    }
+
+    #[test]
+    fn round_trips_outstanding_indices() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        arena.remove(a);
+        let c = arena.insert(3);
+
+        let json = serde_json::to_string(&arena).unwrap();
+        let restored: Arena<i32> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.get(b), Some(&2));
+        assert_eq!(restored.get(c), Some(&3));
+    }
+
+    #[test]
+    fn rejects_free_list_cycle() {
+        let corrupt = Raw {
+            data: vec![
+                Entry::<i32>::Vacant(NonZeroU32::new(1)),
+                Entry::<i32>::Vacant(NonZeroU32::new(1)),
+            ],
+        };
+        let json = serde_json::to_string(&corrupt).unwrap();
+        assert!(serde_json::from_str::<Arena<i32>>(&json).is_err());
+    }
+
+    #[test]
+    fn rejects_occupied_slot_zero() {
+        let corrupt = Raw {
+            data: vec![Entry::Occupied(42)],
+        };
+        let json = serde_json::to_string(&corrupt).unwrap();
+        assert!(serde_json::from_str::<Arena<i32>>(&json).is_err());
+    }
 }
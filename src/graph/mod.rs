@@ -1,6 +1,10 @@
 use crate::arena::typed::{Arena, Index};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::num::NonZeroU32;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
 pub enum Direction {
     Outgoing,
@@ -14,9 +18,19 @@ impl Direction {
             Direction::Incoming => 1,
         }
     }
+
+    /// The direction a traversal walks to find the *other* endpoint of an
+    /// edge reached while following `self`
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Outgoing => Direction::Incoming,
+            Direction::Incoming => Direction::Outgoing,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Edge<E> {
     pub vertices: [VertexIndex; 2],
     pub next: [EdgeIndex; 2],
@@ -37,6 +51,7 @@ impl<E> Edge<E> {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vertex<V> {
     pub edges: [EdgeIndex; 2],
     pub data: V,
@@ -55,12 +70,15 @@ impl<V> Vertex<V> {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EdgeIndex(Option<Index>);
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VertexIndex(Index);
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Graph<V, E> {
     arena: Arena<Vertex<V>>,
     edges: Arena<Edge<E>>,
@@ -95,11 +113,11 @@ impl<V, E> Graph<V, E> {
     }
 
     pub fn edges(&self) -> impl Iterator<Item = &Edge<E>> {
-        self.edges.iter()
+        self.edges.iter().map(|(_, e)| e)
     }
 
     pub fn vertices(&self) -> impl Iterator<Item = &Vertex<V>> {
-        self.arena.iter()
+        self.arena.iter().map(|(_, v)| v)
     }
 
     pub fn get_vertex(&self, index: VertexIndex) -> Option<&Vertex<V>> {
@@ -109,4 +127,423 @@ impl<V, E> Graph<V, E> {
     pub fn get_edge(&self, index: EdgeIndex) -> Option<&Edge<E>> {
         self.edges.get(index.0?)
     }
+
+    /// Walk the intrusive edge chain leaving (or entering) `vertex` in the
+    /// given `dir`, yielding each edge alongside the vertex at its other
+    /// end. Reuses the arena's own linked edge list, so this allocates
+    /// nothing beyond the iterator itself.
+    pub fn neighbors(&self, vertex: VertexIndex, dir: Direction) -> Neighbors<'_, V, E> {
+        let current = self
+            .get_vertex(vertex)
+            .map(|v| v.edge(dir))
+            .unwrap_or(EdgeIndex(None));
+        Neighbors {
+            graph: self,
+            dir,
+            current,
+        }
+    }
+
+    /// Iterate over vertices reachable from `start` in breadth-first order
+    pub fn bfs(&self, start: VertexIndex) -> Bfs<'_, V, E> {
+        let mut queue = VecDeque::new();
+        let mut visited = HashSet::new();
+        queue.push_back(start);
+        visited.insert(start);
+        Bfs {
+            graph: self,
+            queue,
+            visited,
+        }
+    }
+
+    /// Iterate over vertices reachable from `start` in depth-first order
+    pub fn dfs(&self, start: VertexIndex) -> Dfs<'_, V, E> {
+        Dfs {
+            graph: self,
+            stack: vec![start],
+            visited: HashSet::new(),
+        }
+    }
+
+    /// Order all vertices such that every edge points from an earlier
+    /// vertex to a later one, via Kahn's algorithm over in-degrees.
+    ///
+    /// Returns `Err(Cycle)` identifying a vertex that is still part of a
+    /// cycle if no such ordering exists.
+    pub fn topological_sort(&self) -> Result<Vec<VertexIndex>, Cycle> {
+        let mut in_degree: HashMap<VertexIndex, usize> = self
+            .arena
+            .iter()
+            .map(|(idx, _)| (VertexIndex(idx), 0))
+            .collect();
+
+        for edge in self.edges.iter().map(|(_, e)| e) {
+            *in_degree.entry(edge.vertex(Direction::Incoming)).or_insert(0) += 1;
+        }
+
+        let mut queue: VecDeque<VertexIndex> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&v, _)| v)
+            .collect();
+
+        let mut order = Vec::with_capacity(in_degree.len());
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for (_, neighbor) in self.neighbors(v, Direction::Outgoing) {
+                let degree = in_degree.get_mut(&neighbor).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        if order.len() == in_degree.len() {
+            Ok(order)
+        } else {
+            let ordered: HashSet<VertexIndex> = order.into_iter().collect();
+            let mut vertex = in_degree
+                .keys()
+                .copied()
+                .find(|v| !ordered.contains(v))
+                .expect("fewer vertices ordered than exist means one was left out");
+            // Every unordered vertex has an unordered predecessor, so stepping
+            // back once per vertex is certain to end on a cycle.
+            for _ in 0..in_degree.len() {
+                vertex = self
+                    .neighbors(vertex, Direction::Incoming)
+                    .map(|(_, v)| v)
+                    .find(|v| !ordered.contains(v))
+                    .expect("an unordered vertex has an unordered predecessor");
+            }
+            Err(Cycle { vertex })
+        }
+    }
+
+    /// Partition vertices into weakly-connected components, treating every
+    /// edge as undirected
+    pub fn connected_components(&self) -> Vec<Vec<VertexIndex>> {
+        let mut visited = HashSet::new();
+        let mut components = Vec::new();
+
+        for (idx, _) in self.arena.iter() {
+            let start = VertexIndex(idx);
+            if visited.contains(&start) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut stack = vec![start];
+            while let Some(v) = stack.pop() {
+                if !visited.insert(v) {
+                    continue;
+                }
+                component.push(v);
+                for dir in [Direction::Outgoing, Direction::Incoming] {
+                    for (_, neighbor) in self.neighbors(v, dir) {
+                        if !visited.contains(&neighbor) {
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Partition vertices into strongly-connected components via
+    /// Kosaraju's algorithm: a depth-first pass recording finish order,
+    /// followed by a depth-first pass over the transpose (walking
+    /// `Incoming` edges) in reverse finish order.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<VertexIndex>> {
+        let mut visited = HashSet::new();
+        let mut finish_order = Vec::new();
+
+        for (idx, _) in self.arena.iter() {
+            let start = VertexIndex(idx);
+            if !visited.contains(&start) {
+                self.finish_order_dfs(start, &mut visited, &mut finish_order);
+            }
+        }
+
+        let mut assigned = HashSet::new();
+        let mut components = Vec::new();
+        for &v in finish_order.iter().rev() {
+            if assigned.contains(&v) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut stack = vec![v];
+            while let Some(u) = stack.pop() {
+                if !assigned.insert(u) {
+                    continue;
+                }
+                component.push(u);
+                for (_, neighbor) in self.neighbors(u, Direction::Incoming) {
+                    if !assigned.contains(&neighbor) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Iterative post-order depth-first walk, appending each vertex to
+    /// `finish_order` once all of its outgoing neighbors have been visited
+    fn finish_order_dfs(
+        &self,
+        start: VertexIndex,
+        visited: &mut HashSet<VertexIndex>,
+        finish_order: &mut Vec<VertexIndex>,
+    ) {
+        let mut stack = vec![(start, false)];
+        while let Some((v, expanded)) = stack.pop() {
+            if expanded {
+                finish_order.push(v);
+                continue;
+            }
+            if !visited.insert(v) {
+                continue;
+            }
+            stack.push((v, true));
+            for (_, neighbor) in self.neighbors(v, Direction::Outgoing) {
+                if !visited.contains(&neighbor) {
+                    stack.push((neighbor, false));
+                }
+            }
+        }
+    }
+}
+
+/// Error returned by [`Graph::topological_sort`] when the graph contains a
+/// cycle, identifying a vertex that could not be ordered
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cycle {
+    pub vertex: VertexIndex,
+}
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "graph contains a cycle reachable from {:?}", self.vertex)
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// Iterator over `(EdgeIndex, VertexIndex)` pairs produced by
+/// [`Graph::neighbors`], walking the intrusive per-vertex edge chain
+pub struct Neighbors<'a, V, E> {
+    graph: &'a Graph<V, E>,
+    dir: Direction,
+    current: EdgeIndex,
+}
+
+impl<'a, V, E> Iterator for Neighbors<'a, V, E> {
+    type Item = (EdgeIndex, VertexIndex);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge_index = self.current;
+        let edge = self.graph.get_edge(edge_index)?;
+        self.current = edge.next_edge(self.dir);
+        Some((edge_index, edge.vertex(self.dir.opposite())))
+    }
+}
+
+/// Breadth-first traversal over a [`Graph`], following `Outgoing` edges
+pub struct Bfs<'a, V, E> {
+    graph: &'a Graph<V, E>,
+    queue: VecDeque<VertexIndex>,
+    visited: HashSet<VertexIndex>,
+}
+
+impl<'a, V, E> Iterator for Bfs<'a, V, E> {
+    type Item = VertexIndex;
+
+    fn next(&mut self) -> Option<VertexIndex> {
+        let current = self.queue.pop_front()?;
+        for (_, neighbor) in self.graph.neighbors(current, Direction::Outgoing) {
+            if self.visited.insert(neighbor) {
+                self.queue.push_back(neighbor);
+            }
+        }
+        Some(current)
+    }
+}
+
+/// Depth-first traversal over a [`Graph`], following `Outgoing` edges
+pub struct Dfs<'a, V, E> {
+    graph: &'a Graph<V, E>,
+    stack: Vec<VertexIndex>,
+    visited: HashSet<VertexIndex>,
+}
+
+impl<'a, V, E> Iterator for Dfs<'a, V, E> {
+    type Item = VertexIndex;
+
+    fn next(&mut self) -> Option<VertexIndex> {
+        loop {
+            let current = self.stack.pop()?;
+            if !self.visited.insert(current) {
+                continue;
+            }
+            for (_, neighbor) in self.graph.neighbors(current, Direction::Outgoing) {
+                if !self.visited.contains(&neighbor) {
+                    self.stack.push(neighbor);
+                }
+            }
+            return Some(current);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn neighbors_respects_direction() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        let c = graph.add_vertex('c');
+        graph.add_edge(a, b, ());
+        graph.add_edge(a, c, ());
+
+        let outgoing: HashSet<VertexIndex> = graph
+            .neighbors(a, Direction::Outgoing)
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(outgoing, HashSet::from([b, c]));
+
+        let incoming: Vec<VertexIndex> = graph
+            .neighbors(b, Direction::Incoming)
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(incoming, vec![a]);
+    }
+
+    #[test]
+    fn bfs_and_dfs_visit_every_reachable_vertex() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        let c = graph.add_vertex('c');
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+
+        let bfs: HashSet<VertexIndex> = graph.bfs(a).collect();
+        assert_eq!(bfs, HashSet::from([a, b, c]));
+
+        let dfs: HashSet<VertexIndex> = graph.dfs(a).collect();
+        assert_eq!(dfs, HashSet::from([a, b, c]));
+    }
+
+    #[test]
+    fn topological_sort_orders_a_dag() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        let c = graph.add_vertex('c');
+        graph.add_edge(a, b, ());
+        graph.add_edge(a, c, ());
+        graph.add_edge(b, c, ());
+
+        let order = graph.topological_sort().unwrap();
+        let position = |v: VertexIndex| order.iter().position(|&x| x == v).unwrap();
+        assert!(position(a) < position(b));
+        assert!(position(b) < position(c));
+    }
+
+    #[test]
+    fn topological_sort_detects_a_cycle() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, a, ());
+
+        let err = graph.topological_sort().unwrap_err();
+        assert!(err.vertex == a || err.vertex == b);
+    }
+
+    #[test]
+    fn strongly_connected_components_groups_a_cycle_separately() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        let c = graph.add_vertex('c');
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, a, ());
+        graph.add_edge(b, c, ());
+
+        let sccs = graph.strongly_connected_components();
+        let components: Vec<HashSet<VertexIndex>> =
+            sccs.into_iter().map(|c| c.into_iter().collect()).collect();
+
+        assert_eq!(components.len(), 2);
+        assert!(components.contains(&HashSet::from([a, b])));
+        assert!(components.contains(&HashSet::from([c])));
+    }
+
+    #[test]
+    fn connected_components_includes_isolated_vertices() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        let c = graph.add_vertex('c');
+        graph.add_edge(a, b, ());
+
+        let ccs = graph.connected_components();
+        let components: Vec<HashSet<VertexIndex>> =
+            ccs.into_iter().map(|c| c.into_iter().collect()).collect();
+
+        assert_eq!(components.len(), 2);
+        assert!(components.contains(&HashSet::from([a, b])));
+        assert!(components.contains(&HashSet::from([c])));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_test {
+    use super::*;
+
+    #[test]
+    fn round_trips_outstanding_indices() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        let a = graph.add_vertex('a');
+        let b = graph.add_vertex('b');
+        graph.add_edge(a, b, ());
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: Graph<char, ()> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.get_vertex(a).map(|v| v.data), Some('a'));
+        assert_eq!(restored.get_vertex(b).map(|v| v.data), Some('b'));
+        let neighbors: Vec<VertexIndex> = restored
+            .neighbors(a, Direction::Outgoing)
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(neighbors, vec![b]);
+    }
+
+    #[test]
+    fn rejects_corrupt_vertex_arena() {
+        let mut graph: Graph<char, ()> = Graph::with_capacity(8);
+        graph.add_vertex('a');
+
+        let mut value = serde_json::to_value(&graph).unwrap();
+        // Slot 0 is the vertex arena's free-list head and must stay vacant;
+        // overwrite it with the real occupied entry from slot 1 to corrupt it.
+        let occupied_entry = value["arena"]["data"][1].clone();
+        value["arena"]["data"][0] = occupied_entry;
+        let json = serde_json::to_string(&value).unwrap();
+
+        assert!(serde_json::from_str::<Graph<char, ()>>(&json).is_err());
+    }
 }